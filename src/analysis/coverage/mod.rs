@@ -0,0 +1,380 @@
+use {
+    super::*,
+    crate::*,
+    lazy_regex::*,
+    std::collections::HashMap,
+};
+
+/// Which coverage tool produced the output lines we're looking at.
+///
+/// Tarpaulin and `cargo llvm-cov` use different report formats, so
+/// whatever selects a `CoverageReporter` for a job must know which one
+/// it expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverageReporter {
+    Tarpaulin,
+    LlvmCov,
+}
+
+/// Coverage counts for one source file.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FileCoverage {
+    pub path: String,
+    pub covered_lines: usize,
+    pub total_lines: usize,
+    /// Line numbers known to be uncovered, e.g. from tarpaulin's
+    /// `Uncovered Lines:` section.
+    pub uncovered_lines: Vec<usize>,
+}
+
+impl FileCoverage {
+    pub fn percent(&self) -> f64 {
+        if self.total_lines == 0 {
+            100.0
+        } else {
+            100.0 * self.covered_lines as f64 / self.total_lines as f64
+        }
+    }
+}
+
+/// The overall coverage figure printed at the end of a run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProjectCoverage {
+    pub percent: f64,
+    pub covered_lines: usize,
+    pub total_lines: usize,
+}
+
+/// Accumulates per-file and overall coverage data across a run.
+#[derive(Debug, Clone, Default)]
+pub struct CoverageReport {
+    files: HashMap<String, FileCoverage>,
+    /// Uncovered lines seen before their file's covered/total counts,
+    /// since tarpaulin prints the `Uncovered Lines:` section first.
+    pending_uncovered: HashMap<String, Vec<usize>>,
+    in_uncovered_section: bool,
+    project: Option<ProjectCoverage>,
+}
+
+impl CoverageReport {
+    fn record_file(
+        &mut self,
+        mut file: FileCoverage,
+    ) -> &FileCoverage {
+        if let Some(lines) = self.pending_uncovered.remove(&file.path) {
+            file.uncovered_lines.extend(lines);
+        }
+        self.files.entry(file.path.clone()).or_insert(file)
+    }
+
+    fn record_uncovered_lines(
+        &mut self,
+        path: String,
+        lines: Vec<usize>,
+    ) {
+        if let Some(file) = self.files.get_mut(&path) {
+            file.uncovered_lines.extend(lines);
+        } else {
+            self.pending_uncovered.entry(path).or_default().extend(lines);
+        }
+    }
+
+    fn record_project(
+        &mut self,
+        project: ProjectCoverage,
+    ) {
+        self.project = Some(project);
+    }
+
+    /// The files seen so far, lowest-coverage first.
+    pub fn files_by_coverage(&self) -> Vec<&FileCoverage> {
+        let mut files: Vec<&FileCoverage> = self.files.values().collect();
+        files.sort_by(|a, b| a.percent().total_cmp(&b.percent()));
+        files
+    }
+
+    /// The overall project coverage, once its summary line has been seen.
+    pub fn project(&self) -> Option<ProjectCoverage> {
+        self.project
+    }
+}
+
+pub fn analyze_line(
+    cmd_line: &CommandOutputLine,
+    reporter: CoverageReporter,
+    report: &mut CoverageReport,
+) -> LineAnalysis {
+    let Some(content) = cmd_line.content.if_unstyled() else {
+        return standard::analyze_line(cmd_line);
+    };
+    match reporter {
+        CoverageReporter::Tarpaulin => analyze_tarpaulin_line(content, report),
+        CoverageReporter::LlvmCov => analyze_llvm_cov_line(content, report),
+    }
+}
+
+fn analyze_tarpaulin_line(
+    content: &str,
+    report: &mut CoverageReport,
+) -> LineAnalysis {
+    if is_uncovered_lines_header(content) {
+        report.in_uncovered_section = true;
+        return LineAnalysis::of_type(LineType::Garbage);
+    }
+    if report.in_uncovered_section {
+        if let Some((path, lines)) = parse_tarpaulin_uncovered_row(content) {
+            let line = lines.first().copied();
+            report.record_uncovered_lines(path.clone(), lines);
+            return match line {
+                Some(line) => LineAnalysis::location(path, line),
+                None => LineAnalysis::of_type(LineType::Normal),
+            };
+        }
+        report.in_uncovered_section = false;
+    }
+    if let Some(file) = parse_tarpaulin_file_line(content) {
+        let file = report.record_file(file);
+        let line = file.uncovered_lines.first().copied().unwrap_or(1);
+        return LineAnalysis::location(file.path.clone(), line);
+    }
+    if let Some(project) = parse_tarpaulin_summary(content) {
+        report.record_project(project);
+        return LineAnalysis::of_type(LineType::Normal);
+    }
+    LineAnalysis::of_type(LineType::Normal)
+}
+
+fn analyze_llvm_cov_line(
+    content: &str,
+    report: &mut CoverageReport,
+) -> LineAnalysis {
+    if let Some(project) = parse_llvm_cov_summary(content) {
+        report.record_project(project);
+        return LineAnalysis::of_type(LineType::Normal);
+    }
+    if let Some(file) = parse_llvm_cov_row(content) {
+        let file = report.record_file(file);
+        let line = file.uncovered_lines.first().copied().unwrap_or(1);
+        return LineAnalysis::location(file.path.clone(), line);
+    }
+    LineAnalysis::of_type(LineType::Normal)
+}
+
+/// Whether the line is tarpaulin's `|| Uncovered Lines:` section header.
+fn is_uncovered_lines_header(content: &str) -> bool {
+    let content = content.trim().trim_start_matches("||").trim();
+    content == "Uncovered Lines:"
+}
+
+/// Parse a tarpaulin uncovered-lines row like `|| src/foo.rs: 5, 9-12`
+/// into its path and the (expanded) line numbers.
+fn parse_tarpaulin_uncovered_row(content: &str) -> Option<(String, Vec<usize>)> {
+    let (_, path, list) = regex_captures!(r"^\|\| (\S+): ([\d,\s-]+)$", content.trim())?;
+    let lines = parse_line_list(list);
+    if lines.is_empty() {
+        return None;
+    }
+    Some((path.to_string(), lines))
+}
+
+/// Expand a comma-separated list of line numbers and ranges, e.g.
+/// `5, 9-12` into `[5, 9, 10, 11, 12]`.
+fn parse_line_list(list: &str) -> Vec<usize> {
+    let mut lines = Vec::new();
+    for part in list.split(',') {
+        let part = part.trim();
+        if let Some((start, end)) = part.split_once('-') {
+            let (Ok(start), Ok(end)) = (start.trim().parse(), end.trim().parse()) else {
+                continue;
+            };
+            lines.extend(start..=end);
+        } else if let Ok(line) = part.parse() {
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+/// Parse a tarpaulin per-file line like `|| src/foo.rs: 12/40`.
+fn parse_tarpaulin_file_line(content: &str) -> Option<FileCoverage> {
+    let (_, path, covered, total) = regex_captures!(r"^\|\| (\S+): (\d+)/(\d+)$", content.trim())?;
+    Some(FileCoverage {
+        path: path.to_string(),
+        covered_lines: covered.parse().ok()?,
+        total_lines: total.parse().ok()?,
+        uncovered_lines: Vec::new(),
+    })
+}
+
+/// Parse tarpaulin's trailing summary, e.g.
+/// `53.21% coverage, 712/1338 lines covered`.
+fn parse_tarpaulin_summary(content: &str) -> Option<ProjectCoverage> {
+    let (_, percent, covered, total) = regex_captures!(
+        r"^(\d+(?:\.\d+)?)% coverage, (\d+)/(\d+) lines covered$",
+        content.trim()
+    )?;
+    Some(ProjectCoverage {
+        percent: percent.parse().ok()?,
+        covered_lines: covered.parse().ok()?,
+        total_lines: total.parse().ok()?,
+    })
+}
+
+/// Parse one data row of a `cargo llvm-cov` table, e.g.
+/// `src/foo.rs    4    1    75.00%    40    5    87.50%`
+/// (filename, regions, missed regions, region %, lines, missed lines, line %).
+fn parse_llvm_cov_row(content: &str) -> Option<FileCoverage> {
+    let fields: Vec<&str> = content.split_whitespace().collect();
+    if fields.len() < 7 {
+        return None;
+    }
+    let path = fields[0];
+    if !path.ends_with(".rs") {
+        return None;
+    }
+    let total_lines: usize = fields[4].parse().ok()?;
+    let missed_lines: usize = fields[5].parse().ok()?;
+    Some(FileCoverage {
+        path: path.to_string(),
+        covered_lines: total_lines.checked_sub(missed_lines)?,
+        total_lines,
+        uncovered_lines: Vec::new(),
+    })
+}
+
+#[test]
+fn test_parse_tarpaulin_file_line() {
+    assert_eq!(
+        parse_tarpaulin_file_line("|| src/foo.rs: 12/40"),
+        Some(FileCoverage {
+            path: "src/foo.rs".to_string(),
+            covered_lines: 12,
+            total_lines: 40,
+            uncovered_lines: Vec::new(),
+        })
+    );
+    assert_eq!(parse_tarpaulin_file_line("not a coverage line"), None);
+}
+
+#[test]
+fn test_parse_tarpaulin_summary() {
+    assert_eq!(
+        parse_tarpaulin_summary("53.21% coverage, 712/1338 lines covered"),
+        Some(ProjectCoverage {
+            percent: 53.21,
+            covered_lines: 712,
+            total_lines: 1338,
+        })
+    );
+    assert_eq!(parse_tarpaulin_summary("not a summary line"), None);
+}
+
+/// Parse the `cargo llvm-cov` table's trailing `TOTAL` row into the
+/// overall project coverage, e.g.
+/// `TOTAL    120    30    75.00%    900    120    86.66%`.
+fn parse_llvm_cov_summary(content: &str) -> Option<ProjectCoverage> {
+    let fields: Vec<&str> = content.split_whitespace().collect();
+    if fields.len() < 7 || fields[0] != "TOTAL" {
+        return None;
+    }
+    let total_lines: usize = fields[4].parse().ok()?;
+    let missed_lines: usize = fields[5].parse().ok()?;
+    let percent: f64 = fields[6].trim_end_matches('%').parse().ok()?;
+    Some(ProjectCoverage {
+        percent,
+        covered_lines: total_lines.checked_sub(missed_lines)?,
+        total_lines,
+    })
+}
+
+#[test]
+fn test_parse_llvm_cov_row() {
+    assert_eq!(
+        parse_llvm_cov_row("src/foo.rs 4 1 75.00% 40 5 87.50%"),
+        Some(FileCoverage {
+            path: "src/foo.rs".to_string(),
+            covered_lines: 35,
+            total_lines: 40,
+            uncovered_lines: Vec::new(),
+        })
+    );
+    assert_eq!(parse_llvm_cov_row("TOTAL 120 30 75.00% 900 120 86.66%"), None);
+}
+
+#[test]
+fn test_parse_llvm_cov_summary() {
+    assert_eq!(
+        parse_llvm_cov_summary("TOTAL 120 30 75.00% 900 120 86.66%"),
+        Some(ProjectCoverage {
+            percent: 86.66,
+            covered_lines: 780,
+            total_lines: 900,
+        })
+    );
+    assert_eq!(
+        parse_llvm_cov_summary("src/foo.rs 4 1 75.00% 40 5 87.50%"),
+        None
+    );
+}
+
+#[test]
+fn test_parse_line_list() {
+    assert_eq!(parse_line_list("5, 9-12"), vec![5, 9, 10, 11, 12]);
+    assert_eq!(parse_line_list("3"), vec![3]);
+}
+
+#[test]
+fn test_parse_tarpaulin_uncovered_row() {
+    assert_eq!(
+        parse_tarpaulin_uncovered_row("|| src/foo.rs: 5, 9-12"),
+        Some(("src/foo.rs".to_string(), vec![5, 9, 10, 11, 12]))
+    );
+    assert_eq!(parse_tarpaulin_uncovered_row("not a row"), None);
+}
+
+#[test]
+fn test_coverage_report_merges_uncovered_lines_seen_before_the_file_line() {
+    let mut report = CoverageReport::default();
+    report.in_uncovered_section = true;
+    analyze_tarpaulin_line("|| src/foo.rs: 5, 9-12", &mut report);
+    analyze_tarpaulin_line("|| src/foo.rs: 30/40", &mut report);
+    assert_eq!(
+        report.files.get("src/foo.rs").unwrap().uncovered_lines,
+        vec![5, 9, 10, 11, 12]
+    );
+}
+
+#[test]
+fn test_coverage_report_files_by_coverage_and_project() {
+    let mut report = CoverageReport::default();
+    report.record_file(FileCoverage {
+        path: "src/high.rs".to_string(),
+        covered_lines: 9,
+        total_lines: 10,
+        uncovered_lines: Vec::new(),
+    });
+    report.record_file(FileCoverage {
+        path: "src/low.rs".to_string(),
+        covered_lines: 1,
+        total_lines: 10,
+        uncovered_lines: Vec::new(),
+    });
+    let files = report.files_by_coverage();
+    assert_eq!(files[0].path, "src/low.rs");
+    assert_eq!(files[1].path, "src/high.rs");
+
+    assert_eq!(report.project(), None);
+    report.record_project(ProjectCoverage {
+        percent: 53.21,
+        covered_lines: 712,
+        total_lines: 1338,
+    });
+    assert_eq!(
+        report.project(),
+        Some(ProjectCoverage {
+            percent: 53.21,
+            covered_lines: 712,
+            total_lines: 1338,
+        })
+    );
+}
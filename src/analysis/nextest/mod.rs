@@ -2,6 +2,10 @@ use {
     super::*,
     crate::*,
     lazy_regex::*,
+    std::{
+        collections::HashMap,
+        time::Duration,
+    },
 };
 
 const CSI_TITLE: &str = "\u{1b}[35;1m";
@@ -13,7 +17,7 @@ pub fn analyze_line(cmd_line: &CommandOutputLine) -> LineAnalysis {
     if let Some(key) = title_key(content) {
         return LineAnalysis::title_key(Kind::TestFail, key);
     }
-    if let Some((key, pass)) = as_test_result(content) {
+    if let Some((key, pass, _duration)) = as_test_result(content) {
         return LineAnalysis::test_result(key, pass);
     }
     if is_canceling(content) {
@@ -34,6 +38,20 @@ pub fn analyze_line(cmd_line: &CommandOutputLine) -> LineAnalysis {
     standard::analyze_line(cmd_line)
 }
 
+/// Like `analyze_line`, but also records the test's outcome and duration
+/// into `outcomes`/`durations`.
+pub fn analyze_line_tracked(
+    cmd_line: &CommandOutputLine,
+    outcomes: &mut TestOutcomes,
+    durations: &mut TestDurations,
+) -> LineAnalysis {
+    if let Some((key, pass, duration)) = as_test_result(&cmd_line.content) {
+        outcomes.record(key.clone(), pass);
+        durations.record(key, duration);
+    }
+    analyze_line(cmd_line)
+}
+
 /// Return the key when the line is like "--- STD(OUT|ERR): somekey ---"
 fn title_key(content: &TLine) -> Option<String> {
     let mut strings = content.strings.iter();
@@ -86,11 +104,10 @@ fn is_canceling(content: &TLine) -> bool {
     first.csi == CSI_ERROR && first.raw.trim() == "Canceling"
 }
 
-/// return the key and whether the tests passes, when the line is a test
-/// result (like "    PASS [   0.003s] bacon tests::failing_test3")
-///
-/// In the future, we might want to return the duration too.
-fn as_test_result(content: &TLine) -> Option<(String, bool)> {
+/// return the key, whether the test passed, and how long it took, when
+/// the line is a test result (like
+/// "    PASS [   0.003s] bacon tests::failing_test3")
+fn as_test_result(content: &TLine) -> Option<(String, bool, Duration)> {
     let mut strings = content.strings.iter();
     let first = strings.next()?;
     let pass = match (first.csi.as_str(), first.raw.trim()) {
@@ -98,12 +115,116 @@ fn as_test_result(content: &TLine) -> Option<(String, bool)> {
         (CSI_ERROR, "FAIL") => false,
         _ => return None,
     };
-    let _duration = match strings.next() {
-        Some(s) if s.csi.is_empty() => s.raw.trim(),
+    let duration = match strings.next() {
+        Some(s) if s.csi.is_empty() => parse_duration(s.raw.trim())?,
         _ => return None,
     };
     let key = extract_key_after_crate_name(strings)?;
-    Some((key, pass))
+    Some((key, pass, duration))
+}
+
+/// Parse a bracketed nextest duration like "[   0.003s]" into a `Duration`.
+fn parse_duration(raw: &str) -> Option<Duration> {
+    let (_, seconds) = regex_captures!(r"^\[\s*(\d+(?:\.\d+)?)s\]$", raw)?;
+    Some(Duration::from_secs_f64(seconds.parse().ok()?))
+}
+
+/// Tracks which tests passed or failed during the current mission.
+#[derive(Debug, Clone, Default)]
+pub struct TestOutcomes {
+    outcomes: HashMap<String, bool>,
+}
+
+impl TestOutcomes {
+    /// Record (or overwrite) the outcome of a test.
+    pub fn record(
+        &mut self,
+        key: String,
+        pass: bool,
+    ) {
+        self.outcomes.insert(key, pass);
+    }
+
+    /// Keys of the tests which failed on their last run.
+    pub fn failed_keys(&self) -> Vec<&str> {
+        self.outcomes
+            .iter()
+            .filter(|(_, &pass)| !pass)
+            .map(|(key, _)| key.as_str())
+            .collect()
+    }
+
+    /// Whether every test recorded so far passed (also true when empty).
+    pub fn all_passed(&self) -> bool {
+        self.outcomes.values().all(|&pass| pass)
+    }
+
+    /// The nextest filter expression selecting only the tests which
+    /// failed last time, or `None` if everything passed.
+    pub fn rerun_filter_expr(&self) -> Option<String> {
+        nextest_filter_expr(&self.failed_keys())
+    }
+}
+
+/// Accumulates per-test durations across a run.
+#[derive(Debug, Clone, Default)]
+pub struct TestDurations {
+    durations: HashMap<String, Duration>,
+}
+
+impl TestDurations {
+    /// Record (or overwrite) the duration of a test.
+    pub fn record(
+        &mut self,
+        key: String,
+        duration: Duration,
+    ) {
+        self.durations.insert(key, duration);
+    }
+
+    /// The `count` slowest tests, slowest first.
+    pub fn slowest(
+        &self,
+        count: usize,
+    ) -> Vec<(&str, Duration)> {
+        let mut tests: Vec<(&str, Duration)> = self
+            .durations
+            .iter()
+            .map(|(key, &duration)| (key.as_str(), duration))
+            .collect();
+        tests.sort_by(|a, b| b.1.cmp(&a.1));
+        tests.truncate(count);
+        tests
+    }
+
+    /// Tests whose duration is at or above `threshold`, slowest first.
+    pub fn exceeding(
+        &self,
+        threshold: Duration,
+    ) -> Vec<(&str, Duration)> {
+        let mut tests: Vec<(&str, Duration)> = self
+            .durations
+            .iter()
+            .filter(|(_, &duration)| duration >= threshold)
+            .map(|(key, &duration)| (key.as_str(), duration))
+            .collect();
+        tests.sort_by(|a, b| b.1.cmp(&a.1));
+        tests
+    }
+}
+
+/// Build the nextest filter expression selecting exactly the given test
+/// keys, e.g. `test(=key1) + test(=key2)`.
+pub fn nextest_filter_expr(keys: &[&str]) -> Option<String> {
+    if keys.is_empty() {
+        return None;
+    }
+    Some(
+        keys.iter()
+            .map(|key| format!("test(={key})"))
+            .collect::<Vec<_>>()
+            .join(" + "),
+    )
 }
 
 #[test]
@@ -174,7 +295,8 @@ fn test_as_test_result() {
         as_test_result(&content),
         Some((
             "analysis::nextest_analyzer::test_canceling".to_string(),
-            true
+            true,
+            Duration::from_millis(3),
         ))
     );
 }
@@ -189,3 +311,61 @@ fn test_recognize_test_run_failed() {
     };
     assert!(is_error_test_run_failed(&content));
 }
+
+#[test]
+fn test_outcomes_failed_keys_and_all_passed() {
+    let mut outcomes = TestOutcomes::default();
+    assert!(outcomes.all_passed());
+    outcomes.record("tests::a".to_string(), true);
+    assert!(outcomes.all_passed());
+    outcomes.record("tests::b".to_string(), false);
+    assert!(!outcomes.all_passed());
+    assert_eq!(outcomes.failed_keys(), vec!["tests::b"]);
+}
+
+#[test]
+fn test_nextest_filter_expr() {
+    assert_eq!(nextest_filter_expr(&[]), None);
+    assert_eq!(
+        nextest_filter_expr(&["tests::a", "tests::b"]),
+        Some("test(=tests::a) + test(=tests::b)".to_string())
+    );
+}
+
+#[test]
+fn test_outcomes_rerun_filter_expr() {
+    let mut outcomes = TestOutcomes::default();
+    assert_eq!(outcomes.rerun_filter_expr(), None);
+    outcomes.record("tests::a".to_string(), true);
+    outcomes.record("tests::b".to_string(), false);
+    assert_eq!(
+        outcomes.rerun_filter_expr(),
+        Some("test(=tests::b)".to_string())
+    );
+}
+
+#[test]
+fn test_parse_duration() {
+    assert_eq!(parse_duration("[   0.003s]"), Some(Duration::from_millis(3)));
+    assert_eq!(parse_duration("[1.5s]"), Some(Duration::from_millis(1500)));
+    assert_eq!(parse_duration("not a duration"), None);
+}
+
+#[test]
+fn test_durations_slowest_and_exceeding() {
+    let mut durations = TestDurations::default();
+    durations.record("tests::fast".to_string(), Duration::from_millis(3));
+    durations.record("tests::slow".to_string(), Duration::from_millis(500));
+    durations.record("tests::medium".to_string(), Duration::from_millis(50));
+    assert_eq!(
+        durations.slowest(2),
+        vec![
+            ("tests::slow", Duration::from_millis(500)),
+            ("tests::medium", Duration::from_millis(50)),
+        ]
+    );
+    assert_eq!(
+        durations.exceeding(Duration::from_millis(100)),
+        vec![("tests::slow", Duration::from_millis(500))]
+    );
+}
@@ -0,0 +1,129 @@
+use {
+    crate::*,
+    std::path::{
+        Path,
+        PathBuf,
+    },
+};
+
+/// Walk up from `dir` looking for a `.cargo/config.toml` (or the legacy
+/// `.cargo/config`), returning the nearest one found.
+///
+/// Unlike real cargo, this doesn't merge alias tables from every
+/// directory level plus `$CARGO_HOME/config.toml`: only the single
+/// nearest file is read.
+fn find_cargo_config(dir: &Path) -> Option<PathBuf> {
+    let mut dir = Some(dir);
+    while let Some(d) = dir {
+        for name in [".cargo/config.toml", ".cargo/config"] {
+            let path = d.join(name);
+            if path.exists() {
+                return Some(path);
+            }
+        }
+        dir = d.parent();
+    }
+    None
+}
+
+/// Parse the `[alias]` table of a cargo config file into (name, args)
+/// pairs. A cargo alias is either a single space-separated string or a
+/// list of strings.
+fn parse_cargo_aliases(content: &str) -> Vec<(String, Vec<String>)> {
+    let Ok(value) = content.parse::<toml::Value>() else {
+        return Vec::new();
+    };
+    let Some(aliases) = value.get("alias").and_then(|v| v.as_table()) else {
+        return Vec::new();
+    };
+    aliases
+        .iter()
+        .filter_map(|(name, value)| {
+            let args = match value {
+                toml::Value::String(s) => s.split_whitespace().map(str::to_string).collect(),
+                toml::Value::Array(items) => items
+                    .iter()
+                    .filter_map(|item| item.as_str().map(str::to_string))
+                    .collect(),
+                _ => return None,
+            };
+            Some((name.clone(), args))
+        })
+        .collect()
+}
+
+/// Discover the workspace's `.cargo/config.toml` aliases and turn them
+/// into synthesized check-style `Job`s, so e.g. `alias.ci = "clippy
+/// --all-targets"` becomes a runnable `ci` job without it being
+/// re-declared in `bacon.toml`.
+///
+/// This must be applied before the `bacon.toml` files are, so an
+/// explicit job of the same name there still wins.
+pub fn cargo_alias_jobs(package_dir: &Path) -> Vec<(String, Job)> {
+    let Some(config_path) = find_cargo_config(package_dir) else {
+        return Vec::new();
+    };
+    let Ok(content) = std::fs::read_to_string(&config_path) else {
+        return Vec::new();
+    };
+    parse_cargo_aliases(&content)
+        .into_iter()
+        .map(|(name, args)| {
+            let command = std::iter::once("cargo".to_string()).chain(args).collect();
+            (
+                name,
+                Job {
+                    command,
+                    ..Default::default()
+                },
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_parse_cargo_aliases() {
+    let content = r#"
+[alias]
+t = "test"
+ci = ["clippy", "--all-targets"]
+"#;
+    let aliases = parse_cargo_aliases(content);
+    assert_eq!(aliases.len(), 2);
+    assert!(aliases.contains(&("t".to_string(), vec!["test".to_string()])));
+    assert!(aliases.contains(&(
+        "ci".to_string(),
+        vec!["clippy".to_string(), "--all-targets".to_string()]
+    )));
+}
+
+#[test]
+fn test_parse_cargo_aliases_empty_without_alias_table() {
+    assert_eq!(parse_cargo_aliases("[build]\njobs = 4\n"), Vec::new());
+}
+
+#[test]
+fn test_cargo_alias_jobs() {
+    let dir = std::env::temp_dir().join(format!(
+        "bacon-test-cargo-alias-jobs-{}",
+        std::process::id()
+    ));
+    let cargo_dir = dir.join(".cargo");
+    std::fs::create_dir_all(&cargo_dir).unwrap();
+    std::fs::write(
+        cargo_dir.join("config.toml"),
+        "[alias]\nci = [\"clippy\", \"--all-targets\"]\n",
+    )
+    .unwrap();
+
+    let jobs = cargo_alias_jobs(&dir);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(jobs.len(), 1);
+    let (name, job) = &jobs[0];
+    assert_eq!(name, "ci");
+    assert_eq!(
+        job.command,
+        vec!["cargo".to_string(), "clippy".to_string(), "--all-targets".to_string()]
+    );
+}
@@ -81,12 +81,14 @@ impl Settings {
     ///
     ///
     /// Hardcoded defaults are overriden by the following configuration elements, in order:
+    /// * jobs synthesized from the workspace's `.cargo/config.toml` aliases
     /// * the global `prefs.toml`
     /// * the file whose path is in environment variable `BACON_PREFS`
     /// * the workspace level `bacon.toml` file
     /// * the package level `bacon.toml` file
     /// * the file whose path is in environment variable `BACON_CONFIG`
-    /// * args given as arguments, coming from the cli call
+    /// * individual `BACON_*` environment variables, one per setting
+    /// * args given as arguments, coming from the cli call (highest priority)
     pub fn read(
         args: &Args,
         context: &Context,
@@ -96,6 +98,12 @@ impl Settings {
         let default_package_config = Config::default_package_config();
         settings.apply_config(&default_package_config);
 
+        if let Some(package_dir) = context.package_config_path().parent() {
+            for (name, job) in cargo_alias_jobs(package_dir) {
+                settings.jobs.insert(name, job);
+            }
+        }
+
         if let Some(prefs_path) = bacon_prefs_path() {
             if prefs_path.exists() {
                 let prefs = Config::from_path(&prefs_path)?;
@@ -137,6 +145,7 @@ impl Settings {
             settings.apply_config(&config);
         }
 
+        settings.apply_env();
         settings.apply_args(args);
         settings.check()?;
         info!("settings: {:#?}", &settings);
@@ -258,6 +267,35 @@ impl Settings {
             .clone_from(&args.additional_job_args);
     }
 
+    /// Apply individual `BACON_*` environment variable overrides, cargo-config
+    /// style: each one tweaks a single setting without requiring a whole
+    /// config file. This is distinct from `BACON_PREFS`/`BACON_CONFIG`, which
+    /// each point at a whole file. Must be applied before `apply_args` so an
+    /// explicit CLI flag still wins over the environment.
+    pub fn apply_env(&mut self) {
+        if let Some(b) = env_bool("BACON_WRAP") {
+            self.wrap = b;
+        }
+        if let Some(b) = env_bool("BACON_REVERSE") {
+            self.reverse = b;
+        }
+        if let Some(ms) = env_var("BACON_GRACE_PERIOD").and_then(|v| v.parse::<u64>().ok()) {
+            self.grace_period = Duration::from_millis(ms).into();
+        }
+        if let Some(name) = env_var("BACON_DEFAULT_JOB") {
+            self.default_job = ConcreteJobRef {
+                name_or_alias: NameOrAlias::Name(name),
+                ..Default::default()
+            };
+        }
+        if let Some(features) = env_var("BACON_FEATURES") {
+            self.features = Some(features);
+        }
+        if let Some(b) = env_bool("BACON_ALL_FEATURES") {
+            self.all_features = b;
+        }
+    }
+
     pub fn check(&self) -> Result<()> {
         if self.jobs.is_empty() {
             bail!("Invalid configuration : no job found");
@@ -270,3 +308,133 @@ impl Settings {
         Ok(())
     }
 }
+
+/// Read a `BACON_*` environment variable, ignoring it if unset or not
+/// valid unicode.
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+/// Read a `BACON_*` environment variable as a boolean, accepting the
+/// usual `true`/`false`, `1`/`0`, `yes`/`no` spellings.
+fn env_bool(name: &str) -> Option<bool> {
+    match env_var(name)?.to_lowercase().as_str() {
+        "1" | "true" | "yes" => Some(true),
+        "0" | "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod env_tests {
+    use super::*;
+
+    /// Held for the whole of each test of this module, since they all
+    /// read and write the same process-wide `BACON_*` variables.
+    static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    fn lock_env() -> std::sync::MutexGuard<'static, ()> {
+        ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Unset all `BACON_*` vars read by `apply_env`; together with
+    /// `lock_env`, this keeps tests from leaking into each other through
+    /// the shared process environment.
+    fn clear_bacon_env() {
+        for name in [
+            "BACON_WRAP",
+            "BACON_REVERSE",
+            "BACON_GRACE_PERIOD",
+            "BACON_DEFAULT_JOB",
+            "BACON_FEATURES",
+            "BACON_ALL_FEATURES",
+        ] {
+            unsafe { std::env::remove_var(name) };
+        }
+    }
+
+    #[test]
+    fn test_env_var_and_env_bool() {
+        let _env = lock_env();
+        clear_bacon_env();
+        assert_eq!(env_var("BACON_FEATURES"), None);
+        assert_eq!(env_bool("BACON_WRAP"), None);
+
+        unsafe { std::env::set_var("BACON_FEATURES", "foo,bar") };
+        assert_eq!(env_var("BACON_FEATURES"), Some("foo,bar".to_string()));
+
+        for (raw, expected) in [
+            ("1", Some(true)),
+            ("true", Some(true)),
+            ("TRUE", Some(true)),
+            ("yes", Some(true)),
+            ("0", Some(false)),
+            ("false", Some(false)),
+            ("no", Some(false)),
+            ("maybe", None),
+        ] {
+            unsafe { std::env::set_var("BACON_WRAP", raw) };
+            assert_eq!(env_bool("BACON_WRAP"), expected, "input was {raw:?}");
+        }
+
+        clear_bacon_env();
+    }
+
+    #[test]
+    fn test_apply_env_parses_each_key() {
+        let _env = lock_env();
+        clear_bacon_env();
+        unsafe {
+            std::env::set_var("BACON_WRAP", "false");
+            std::env::set_var("BACON_REVERSE", "true");
+            std::env::set_var("BACON_GRACE_PERIOD", "150");
+            std::env::set_var("BACON_DEFAULT_JOB", "check");
+            std::env::set_var("BACON_FEATURES", "foo,bar");
+            std::env::set_var("BACON_ALL_FEATURES", "yes");
+        }
+
+        let mut settings = Settings::default();
+        settings.apply_env();
+
+        assert_eq!(settings.wrap, false);
+        assert_eq!(settings.reverse, true);
+        assert_eq!(settings.grace_period, Duration::from_millis(150).into());
+        let NameOrAlias::Name(name) = &settings.default_job.name_or_alias else {
+            panic!("expected a name, not an alias");
+        };
+        assert_eq!(name, "check");
+        assert_eq!(settings.features, Some("foo,bar".to_string()));
+        assert_eq!(settings.all_features, true);
+
+        clear_bacon_env();
+    }
+
+    #[test]
+    fn test_apply_env_leaves_unset_keys_untouched() {
+        let _env = lock_env();
+        clear_bacon_env();
+        let mut settings = Settings::default();
+        let before = settings.wrap;
+        settings.apply_env();
+        assert_eq!(settings.wrap, before);
+        assert_eq!(settings.features, None);
+    }
+
+    /// `Settings::read` applies `apply_env` after every config file and
+    /// before `apply_args` (see the doc comment on `apply_env`). This
+    /// checks that a value set before `apply_env`, as a config file would,
+    /// is overwritten by the environment; the CLI side is not checked here.
+    #[test]
+    fn test_apply_env_overrides_earlier_config() {
+        let _env = lock_env();
+        clear_bacon_env();
+        unsafe { std::env::set_var("BACON_WRAP", "false") };
+
+        let mut settings = Settings::default();
+        settings.wrap = true; // simulate a prior config file setting it true
+        settings.apply_env();
+        assert_eq!(settings.wrap, false, "BACON_WRAP must override earlier config");
+
+        clear_bacon_env();
+    }
+}